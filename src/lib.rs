@@ -22,6 +22,7 @@ pub mod interrupts;
 pub mod gdt;
 pub mod memory;
 pub mod allocator;
+pub mod sound;
 
 pub trait Testable {
     fn run(&self) -> ();
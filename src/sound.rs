@@ -0,0 +1,44 @@
+use x86_64::instructions::port::Port;
+
+const PIT_FREQUENCY: u32 = 1_193_182;
+const PIT_CHANNEL_2: u16 = 0x42;
+const PIT_COMMAND: u16 = 0x43;
+const SPEAKER_CONTROL: u16 = 0x61;
+
+// Starts a square wave on the PC speaker and returns immediately.
+// The tone keeps playing until `stop_beep` is called.
+pub fn beep(frequency_hz: u32) {
+    if frequency_hz == 0 {
+        stop_beep();
+        return;
+    }
+
+    // the divisor is a 16 bit reload value for PIT channel 2
+    let divisor = (PIT_FREQUENCY / frequency_hz).clamp(1, u16::MAX as u32) as u16;
+
+    let mut command: Port<u8> = Port::new(PIT_COMMAND);
+    let mut channel_2: Port<u8> = Port::new(PIT_CHANNEL_2);
+    let mut speaker: Port<u8> = Port::new(SPEAKER_CONTROL);
+
+    unsafe {
+        // channel 2, lobyte/hibyte access, mode 3 (square wave)
+        command.write(0xb6);
+        channel_2.write(divisor as u8);
+        channel_2.write((divisor >> 8) as u8);
+
+        // connect the PIT to the speaker (gate bit 0, data bit 1)
+        let value = speaker.read();
+        if value & 0b11 != 0b11 {
+            speaker.write(value | 0b11);
+        }
+    }
+}
+
+pub fn stop_beep() {
+    let mut speaker: Port<u8> = Port::new(SPEAKER_CONTROL);
+
+    unsafe {
+        let value = speaker.read();
+        speaker.write(value & !0b11);
+    }
+}
@@ -27,12 +27,32 @@ pub enum Color {
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
-struct ColorCode(u8);
+pub struct ColorCode(u8);
 
 impl ColorCode {
-    fn new(foreground_color: Color, background_color: Color) -> ColorCode {
+    pub fn new(foreground_color: Color, background_color: Color) -> ColorCode {
         ColorCode((background_color as u8) << 4 | foreground_color as u8)
     }
+
+    fn with_foreground(self, foreground_color: Color) -> ColorCode {
+        ColorCode(self.0 & 0xf0 | foreground_color as u8)
+    }
+
+    fn with_background(self, background_color: Color) -> ColorCode {
+        ColorCode((background_color as u8) << 4 | self.0 & 0x0f)
+    }
+
+    // swaps foreground and background, for highlighting
+    pub fn inverted(self) -> ColorCode {
+        ColorCode(self.0.rotate_left(4))
+    }
+
+    // Sets the blink bit (bit 7). In the default VGA attribute mode this
+    // bit is shared with the background, so while blinking is enabled by the
+    // hardware only the first 8 colors are available as backgrounds.
+    pub fn with_blink(self) -> ColorCode {
+        ColorCode(self.0 | 0x80)
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -45,49 +65,164 @@ struct ScreenChar {
 const BUFFER_HEIGHT: usize = 25;
 const BUFFER_WIDTH: usize = 80;
 
+const BACKSPACE: u8 = 0x08;
+const TAB_WIDTH: usize = 4;
+
 #[repr(transparent)]
 struct Buffer {
     chars: [[Volatile<ScreenChar>; BUFFER_WIDTH]; BUFFER_HEIGHT]
 }
 
 pub struct Writer {
+    row_position: usize,
     column_position: usize,
     color_code: ColorCode,
+    // rows (inclusive) that new_line scrolls
+    scroll_top: usize,
+    scroll_bottom: usize,
     buffer: &'static mut Buffer,
 }
 
 impl Writer {
     pub fn write_byte(&mut self, byte: u8) {
+        self.put_byte(byte);
+    }
+
+    // returns the number of cells the byte filled
+    fn put_byte(&mut self, byte: u8) -> usize {
         match byte {
-            b'\n' => self.new_line(),
+            b'\n' => {
+                self.new_line();
+                0
+            },
+            b'\t' => self.tab(),
+            BACKSPACE => {
+                self.backspace();
+                0
+            },
             byte => {
-                if self.column_position >= BUFFER_WIDTH {
-                    self.new_line();
-                }
+                self.write_raw_byte(byte);
+                1
+            },
+        }
+    }
 
-                let row = BUFFER_HEIGHT - 1;
-                let col = self.column_position;
+    // writes the code page 437 glyph for `byte` without interpreting it,
+    // so even control bytes like b'\n' are drawn as symbols
+    pub fn write_raw_byte(&mut self, byte: u8) {
+        if self.column_position >= BUFFER_WIDTH {
+            self.new_line();
+        }
 
-                self.buffer.chars[row][col].write(ScreenChar {
-                    ascii_character: byte,
-                    color_code: self.color_code
-                });
+        let row = self.row_position;
+        let col = self.column_position;
+        debug_assert!(col < BUFFER_WIDTH);
 
-                self.column_position += 1;
-            }
+        self.buffer.chars[row][col].write(ScreenChar {
+            ascii_character: byte,
+            color_code: self.color_code
+        });
+
+        self.column_position += 1;
+    }
+
+    // pads with spaces up to the next tab stop, wrapping like any other
+    // character would
+    fn tab(&mut self) -> usize {
+        let spaces = TAB_WIDTH - self.column_position % TAB_WIDTH;
+        for _ in 0..spaces {
+            self.write_raw_byte(b' ');
         }
+        spaces
     }
 
+    // erases the previous character on the current row
+    fn backspace(&mut self) {
+        if self.column_position > 0 {
+            self.column_position -= 1;
+
+            let row = self.row_position;
+            let col = self.column_position;
+            debug_assert!(col < BUFFER_WIDTH);
+
+            self.buffer.chars[row][col].write(ScreenChar {
+                ascii_character: b' ',
+                color_code: self.color_code
+            });
+        }
+    }
+
+    // moves the cursor down a row, scrolling once it reaches the bottom
+    // of the scroll region; on the last screen row below the region the
+    // cursor stays put and the region is left alone
     fn new_line(&mut self) {
-        for row in 1..BUFFER_HEIGHT {
+        if self.row_position == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.row_position < BUFFER_HEIGHT - 1 {
+            self.row_position += 1;
+        }
+        self.column_position = 0;
+    }
+
+    // moves the cursor that the next write_byte lands on
+    pub fn set_cursor_position(&mut self, row: usize, col: usize) {
+        assert!(row < BUFFER_HEIGHT && col < BUFFER_WIDTH,
+                "cell ({}, {}) is outside the buffer", row, col);
+
+        self.row_position = row;
+        self.column_position = col;
+    }
+
+    pub fn cursor_position(&self) -> (usize, usize) {
+        (self.row_position, self.column_position)
+    }
+
+    // shifts the scroll region up by `n` rows and blanks the rows exposed
+    // at the bottom
+    pub fn scroll_up(&mut self, n: usize) {
+        let n = n.min(self.scroll_bottom - self.scroll_top + 1);
+
+        for row in self.scroll_top + n..=self.scroll_bottom {
             for col in 0..BUFFER_WIDTH {
                 let character = self.buffer.chars[row][col].read();
-                self.buffer.chars[row-1][col].write(character);
+                self.buffer.chars[row-n][col].write(character);
             }
         }
 
-        self.clear_row(BUFFER_HEIGHT-1);
-        self.column_position = 0;
+        for row in self.scroll_bottom + 1 - n..=self.scroll_bottom {
+            self.clear_row(row);
+        }
+    }
+
+    // blanks a rectangle with the current color, clamped to the buffer
+    pub fn clear_region(&mut self, top: usize, left: usize,
+                        height: usize, width: usize) {
+        let blank = ScreenChar {
+            ascii_character: b' ',
+            color_code: self.color_code
+        };
+        let bottom = top.saturating_add(height).min(BUFFER_HEIGHT);
+        let right = left.saturating_add(width).min(BUFFER_WIDTH);
+        for row in top..bottom {
+            for col in left..right {
+                self.buffer.chars[row][col].write(blank);
+            }
+        }
+    }
+
+    // confines scrolling to rows `top..=bottom`, leaving the others fixed,
+    // and moves the cursor to the bottom row of the region
+    pub fn set_scroll_region(&mut self, top: usize, bottom: usize) {
+        assert!(top <= bottom && bottom < BUFFER_HEIGHT,
+                "invalid scroll region {}..={}", top, bottom);
+
+        self.scroll_top = top;
+        self.scroll_bottom = bottom;
+        self.row_position = bottom;
+    }
+
+    pub fn reset_scroll_region(&mut self) {
+        self.set_scroll_region(0, BUFFER_HEIGHT - 1);
     }
 
     fn clear_row(&mut self, row_index: usize) {
@@ -100,13 +235,50 @@ impl Writer {
         }
     }
 
+    // runs `f` with a temporary color, restoring the previous one afterwards
+    pub fn with_color<F: FnOnce(&mut Writer)>(&mut self, fg: Color, bg: Color, f: F) {
+        let previous = self.color_code;
+        self.color_code = ColorCode::new(fg, bg);
+        f(self);
+        self.color_code = previous;
+    }
+
     pub fn write_string(&mut self, s: &str) {
-        for byte in s.bytes() {
-            match byte {
-                b'\n' => self.write_byte(byte),
+        self.write_string_counted(s);
+    }
+
+    // returns the number of cells written, so layout code can tell where
+    // the text ended up (including any wrapping onto new lines)
+    pub fn write_string_counted(&mut self, s: &str) -> usize {
+        self.write_bytes_counted(s.as_bytes())
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_bytes_counted(bytes);
+    }
+
+    fn write_bytes_counted(&mut self, bytes: &[u8]) -> usize {
+        let mut placed = 0;
+        for &byte in bytes {
+            placed += match byte {
+                b'\n' | b'\t' | BACKSPACE => self.put_byte(byte),
                 // inside ascii range
-                0x20..=0x7e => self.write_byte(byte),
+                0x20..=0x7e => self.put_byte(byte),
                 // not in ascii range
+                _ => self.put_byte(0xfe),
+            };
+        }
+        placed
+    }
+
+    // like write_bytes, but passes the upper half of code page 437
+    // (box drawing, accented letters, ...) through unchanged
+    pub fn write_cp437(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            match byte {
+                b'\n' | b'\t' | BACKSPACE => self.write_byte(byte),
+                0x20..=0x7e | 0x80..=0xff => self.write_byte(byte),
+                // control characters
                 _ => self.write_byte(0xfe),
             }
         }
@@ -120,6 +292,60 @@ impl Writer {
             self.clear_row(row_index);
         }
     }
+
+    // switches the background color, keeping the foreground, and
+    // clears the screen so the whole console takes the new color; output
+    // continues from the start of the scroll region's bottom row
+    pub fn set_background(&mut self, bg: Color) {
+        self.color_code = self.color_code.with_background(bg);
+        self.init();
+        self.row_position = self.scroll_bottom;
+        self.column_position = 0;
+    }
+
+    // reads back the ascii characters currently on screen
+    pub fn snapshot(&self) -> [[u8; BUFFER_WIDTH]; BUFFER_HEIGHT] {
+        let mut screen = [[b' '; BUFFER_WIDTH]; BUFFER_HEIGHT];
+        for (screen_row, buffer_row) in screen.iter_mut().zip(self.buffer.chars.iter()) {
+            for (cell, screen_char) in screen_row.iter_mut().zip(buffer_row.iter()) {
+                *cell = screen_char.read().ascii_character;
+            }
+        }
+        screen
+    }
+
+    pub fn write_char_at(&mut self, row: usize, col: usize, byte: u8) {
+        assert!(row < BUFFER_HEIGHT && col < BUFFER_WIDTH,
+                "cell ({}, {}) is outside the buffer", row, col);
+
+        self.buffer.chars[row][col].write(ScreenChar {
+            ascii_character: byte,
+            color_code: self.color_code
+        });
+    }
+
+    // writes `text` centered on `row`, truncating it to the screen width
+    pub fn print_centered(&mut self, row: usize, text: &str, color: ColorCode) {
+        use core::fmt::Write;
+
+        let len = text.len().min(BUFFER_WIDTH);
+        let col = (BUFFER_WIDTH - len) / 2;
+
+        let previous = self.color_code;
+        self.color_code = color;
+        PositionedWriter { writer: self, row, col }
+            .write_str(text)
+            .unwrap();
+        self.color_code = previous;
+    }
+
+    pub fn read_char_at(&self, row: usize, col: usize) -> (u8, ColorCode) {
+        assert!(row < BUFFER_HEIGHT && col < BUFFER_WIDTH,
+                "cell ({}, {}) is outside the buffer", row, col);
+
+        let screen_char = self.buffer.chars[row][col].read();
+        (screen_char.ascii_character, screen_char.color_code)
+    }
 }
 
 impl fmt::Write for Writer {
@@ -129,10 +355,39 @@ impl fmt::Write for Writer {
     }
 }
 
+// writes formatted text from a fixed position without moving the cursor,
+// dropping anything that runs past the right edge of the screen
+struct PositionedWriter<'a> {
+    writer: &'a mut Writer,
+    row: usize,
+    col: usize,
+}
+
+impl fmt::Write for PositionedWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            if self.row >= BUFFER_HEIGHT || self.col >= BUFFER_WIDTH {
+                break;
+            }
+
+            let byte = match byte {
+                0x20..=0x7e => byte,
+                _ => 0xfe,
+            };
+            self.writer.write_char_at(self.row, self.col, byte);
+            self.col += 1;
+        }
+        Ok(())
+    }
+}
+
 lazy_static! {
     pub static ref WRITER: Mutex<Writer> = Mutex::new(Writer {
+        row_position: BUFFER_HEIGHT - 1,
         column_position: 0,
         color_code: ColorCode::new(Color::LightGray, Color::Black),
+        scroll_top: 0,
+        scroll_bottom: BUFFER_HEIGHT - 1,
         buffer: unsafe { &mut *(0xb8000 as *mut Buffer) }
     });
 }
@@ -159,6 +414,50 @@ pub fn _print(args: fmt::Arguments) {
     });
 }
 
+#[macro_export]
+macro_rules! print_at {
+    ($row:expr, $col:expr, $($arg:tt)*) => (
+        $crate::vga_buffer::_print_at($row, $col, format_args!($($arg)*)));
+}
+
+#[doc(hidden)]
+pub fn _print_at(row: usize, col: usize, args: fmt::Arguments) {
+    use core::fmt::Write;
+    use x86_64::instructions::interrupts;
+
+    interrupts::without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        PositionedWriter { writer: &mut writer, row, col }
+            .write_fmt(args)
+            .unwrap()
+    });
+}
+
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => ($crate::vga_buffer::_eprint(format_args!($($arg)*)));
+}
+
+#[macro_export]
+macro_rules! eprintln {
+    () => ($crate::eprint!("\n"));
+    ($($arg:tt)*) => ($crate::eprint!("{}\n", format_args!($($arg)*)));
+}
+
+#[doc(hidden)]
+pub fn _eprint(args: fmt::Arguments) {
+    use core::fmt::Write;
+    use x86_64::instructions::interrupts;
+
+    interrupts::without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let previous = writer.color_code;
+        writer.color_code = previous.with_foreground(Color::Red);
+        writer.write_fmt(args).unwrap();
+        writer.color_code = previous;
+    });
+}
+
 #[test_case]
 fn test_println_simple() {
     println!("foo bar baz");
@@ -186,3 +485,342 @@ fn test_println_output() {
         }
     });
 }
+
+#[test_case]
+fn test_snapshot() {
+    use x86_64::instructions::interrupts::without_interrupts;
+    use core::fmt::Write;
+
+    let s = "snapshot me";
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writeln!(writer, "\n{}", s).expect("writeln failed");
+        let screen = writer.snapshot();
+        assert_eq!(&screen[BUFFER_HEIGHT - 2][..s.len()], s.as_bytes());
+        assert_eq!(screen[BUFFER_HEIGHT - 2][s.len()], b' ');
+        assert!(screen[BUFFER_HEIGHT - 1].iter().all(|&c| c == b' '));
+    });
+}
+
+#[test_case]
+fn test_read_char_at() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        writer.write_string("xy");
+        let (character, color_code) = writer.read_char_at(BUFFER_HEIGHT - 1, 1);
+        assert_eq!(character, b'y');
+        assert_eq!(color_code, writer.color_code);
+    });
+}
+
+#[test_case]
+fn test_write_cp437() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        writer.write_cp437(&[0xc9, b'a', 0x07, 0xbb]);
+        writer.write_raw_byte(0x07);
+        let row = BUFFER_HEIGHT - 1;
+        assert_eq!(writer.read_char_at(row, 0).0, 0xc9);
+        assert_eq!(writer.read_char_at(row, 1).0, b'a');
+        assert_eq!(writer.read_char_at(row, 2).0, 0xfe);
+        assert_eq!(writer.read_char_at(row, 3).0, 0xbb);
+        assert_eq!(writer.read_char_at(row, 4).0, 0x07);
+    });
+}
+
+#[test_case]
+fn test_backspace() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        writer.write_string("abc\x08");
+        assert_eq!(writer.column_position, 2);
+        assert_eq!(writer.read_char_at(BUFFER_HEIGHT - 1, 1).0, b'b');
+        assert_eq!(writer.read_char_at(BUFFER_HEIGHT - 1, 2).0, b' ');
+    });
+}
+
+#[test_case]
+fn test_tab() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        writer.write_string("a\t");
+        assert_eq!(writer.column_position, 4);
+        writer.write_string("\t");
+        assert_eq!(writer.column_position, 8);
+        assert_eq!(writer.read_char_at(BUFFER_HEIGHT - 1, 3).0, b' ');
+    });
+}
+
+#[test_case]
+fn test_scroll_region() {
+    use x86_64::instructions::interrupts::without_interrupts;
+    use core::fmt::Write;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        write!(writer, "\nfixed").expect("write failed");
+        writer.set_scroll_region(0, BUFFER_HEIGHT - 2);
+        writeln!(writer, "\nscrolled").expect("writeln failed");
+        let screen = writer.snapshot();
+        writer.reset_scroll_region();
+
+        assert_eq!(&screen[BUFFER_HEIGHT - 1][..5], b"fixed");
+        assert_eq!(&screen[BUFFER_HEIGHT - 3][..8], b"scrolled");
+        assert!(screen[BUFFER_HEIGHT - 2].iter().all(|&c| c == b' '));
+    });
+}
+
+#[test_case]
+fn test_color_code_inverted() {
+    let color_code = ColorCode::new(Color::LightGray, Color::Blue);
+    assert_eq!(color_code.inverted(), ColorCode::new(Color::Blue, Color::LightGray));
+    assert_eq!(color_code.inverted().inverted(), color_code);
+}
+
+#[test_case]
+fn test_color_code_with_blink() {
+    let color_code = ColorCode::new(Color::Yellow, Color::Blue).with_blink();
+    assert_eq!(color_code.0, 0x80 | 0x1e);
+}
+
+#[test_case]
+fn test_with_color() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let original = writer.color_code;
+        writer.write_byte(b'\n');
+        writer.with_color(Color::Red, Color::White, |w| w.write_string("r"));
+        assert_eq!(writer.color_code, original);
+        let (_, color_code) = writer.read_char_at(BUFFER_HEIGHT - 1, 0);
+        assert_eq!(color_code, ColorCode::new(Color::Red, Color::White));
+    });
+}
+
+#[test_case]
+fn test_eprintln_output() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    eprintln!("\nerror");
+    without_interrupts(|| {
+        let writer = WRITER.lock();
+        let (character, color_code) = writer.read_char_at(BUFFER_HEIGHT - 2, 0);
+        assert_eq!(character, b'e');
+        assert_eq!(color_code, writer.color_code.with_foreground(Color::Red));
+        assert_ne!(writer.color_code, color_code);
+    });
+}
+
+#[test_case]
+fn test_print_at() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    print_at!(3, BUFFER_WIDTH - 6, "at {}", 42);
+    print_at!(4, BUFFER_WIDTH - 3, "clipped");
+    without_interrupts(|| {
+        let writer = WRITER.lock();
+        let screen = writer.snapshot();
+        assert_eq!(&screen[3][BUFFER_WIDTH - 6..BUFFER_WIDTH - 1], b"at 42");
+        assert_eq!(&screen[4][BUFFER_WIDTH - 3..], b"cli");
+    });
+}
+
+#[test_case]
+fn test_print_centered() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let color_code = ColorCode::new(Color::White, Color::Blue);
+        writer.clear_row(5);
+        writer.print_centered(5, "hello", color_code);
+        let screen = writer.snapshot();
+        assert_eq!(&screen[5][36..43], b" hello ");
+        assert_eq!(writer.read_char_at(5, 37).1, color_code);
+        assert_ne!(writer.color_code, color_code);
+
+        let long = [b'x'; BUFFER_WIDTH + 10];
+        writer.print_centered(6, core::str::from_utf8(&long).unwrap(), color_code);
+        assert!(writer.snapshot()[6].iter().all(|&c| c == b'x'));
+    });
+}
+
+#[test_case]
+fn test_write_bytes() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        writer.write_bytes(&[b'o', 0xe9, b'k']);
+        let screen = writer.snapshot();
+        assert_eq!(&screen[BUFFER_HEIGHT - 1][..3], &[b'o', 0xfe, b'k']);
+    });
+}
+
+#[test_case]
+fn test_set_background() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let original = writer.color_code;
+        writer.set_cursor_position(3, 7);
+        writer.set_background(Color::White);
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 0));
+        writer.write_string("x");
+        let light = original.with_background(Color::White);
+        assert_eq!(writer.read_char_at(0, 0).1, light);
+        assert_eq!(writer.read_char_at(BUFFER_HEIGHT - 1, 0), (b'x', light));
+        writer.set_background(Color::Black);
+        assert_eq!(writer.color_code, original);
+    });
+}
+
+#[test_case]
+fn test_write_string_counted() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    let long = [b'x'; BUFFER_WIDTH + 20];
+    let long = core::str::from_utf8(&long).unwrap();
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        assert_eq!(writer.write_string_counted(long), BUFFER_WIDTH + 20);
+        assert_eq!(writer.column_position, 20);
+        assert_eq!(writer.write_string_counted("\n\u{e9}\t"), 4);
+    });
+}
+
+#[test_case]
+fn test_clear_region() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        for row in 10..14 {
+            for col in 10..14 {
+                writer.write_char_at(row, col, b'z');
+            }
+        }
+        writer.clear_region(11, 11, 2, 2);
+        writer.clear_region(BUFFER_HEIGHT - 1, BUFFER_WIDTH - 1, 5, 5);
+        let screen = writer.snapshot();
+        assert_eq!(&screen[10][10..14], b"zzzz");
+        assert_eq!(&screen[11][10..14], b"z  z");
+        assert_eq!(&screen[12][10..14], b"z  z");
+        assert_eq!(&screen[13][10..14], b"zzzz");
+    });
+}
+
+#[test_case]
+fn test_write_byte_wraps_full_row() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_byte(b'\n');
+        for _ in 0..BUFFER_WIDTH {
+            writer.write_byte(b'w');
+        }
+        assert_eq!(writer.column_position, BUFFER_WIDTH);
+        writer.write_byte(b'!');
+        assert_eq!(writer.column_position, 1);
+        let screen = writer.snapshot();
+        assert!(screen[BUFFER_HEIGHT - 2].iter().all(|&c| c == b'w'));
+        assert_eq!(screen[BUFFER_HEIGHT - 1][0], b'!');
+
+        writer.write_byte(b'\n');
+        writer.write_string("\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t");
+        assert_eq!(writer.column_position, TAB_WIDTH);
+    });
+}
+
+#[test_case]
+fn test_scroll_up() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        for row in 0..BUFFER_HEIGHT {
+            writer.write_char_at(row, 0, b'A' + row as u8);
+        }
+        writer.scroll_up(3);
+        let screen = writer.snapshot();
+        for (row, line) in screen.iter().take(BUFFER_HEIGHT - 3).enumerate() {
+            assert_eq!(line[0], b'A' + row as u8 + 3);
+        }
+        assert!(screen[BUFFER_HEIGHT - 3..].iter().flatten().all(|&c| c == b' '));
+
+        writer.scroll_up(BUFFER_HEIGHT + 1);
+        assert!(writer.snapshot().iter().flatten().all(|&c| c == b' '));
+    });
+}
+
+#[test_case]
+fn test_set_cursor_position() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.clear_row(5);
+        writer.clear_row(6);
+        writer.set_cursor_position(5, BUFFER_WIDTH - 2);
+        writer.write_string("abc");
+        assert_eq!(writer.cursor_position(), (6, 1));
+        writer.write_string("\nd");
+        assert_eq!(writer.cursor_position(), (7, 1));
+        let screen = writer.snapshot();
+        assert_eq!(&screen[5][BUFFER_WIDTH - 2..], b"ab");
+        assert_eq!(screen[6][0], b'c');
+        assert_eq!(screen[7][0], b'd');
+
+        // writing past the last row scrolls like println! does
+        writer.set_cursor_position(BUFFER_HEIGHT - 1, BUFFER_WIDTH - 1);
+        writer.write_string("yz");
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 1));
+        let screen = writer.snapshot();
+        assert_eq!(screen[BUFFER_HEIGHT - 2][BUFFER_WIDTH - 1], b'y');
+        assert_eq!(screen[BUFFER_HEIGHT - 1][0], b'z');
+        assert_eq!(screen[6][0], b'd');
+    });
+}
+
+#[test_case]
+fn test_new_line_below_scroll_region() {
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.set_scroll_region(0, 10);
+        writer.write_char_at(0, 0, b'T');
+        writer.clear_row(BUFFER_HEIGHT - 1);
+        writer.set_cursor_position(BUFFER_HEIGHT - 1, 5);
+        writer.write_string("\nS");
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 1));
+
+        // a wrapping status line stays on its row
+        writer.set_cursor_position(BUFFER_HEIGHT - 1, BUFFER_WIDTH - 1);
+        writer.write_string("xy");
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 1));
+        let screen = writer.snapshot();
+        writer.reset_scroll_region();
+
+        assert_eq!(screen[0][0], b'T');
+        assert_eq!(screen[BUFFER_HEIGHT - 1][0], b'y');
+        assert_eq!(screen[BUFFER_HEIGHT - 1][BUFFER_WIDTH - 1], b'x');
+    });
+}